@@ -0,0 +1,76 @@
+use crate::{Boundary, Field};
+use ::geojson::{Feature, FeatureCollection, Geometry, Value};
+use serde_json::{json, Map};
+
+/// Builds a `FeatureCollection` mirroring the KML/KMZ output: one polygon and one center-line
+/// feature per `Field`, plus a single line feature for the survey `Boundary`.
+pub(crate) fn build(fields: &[Field], boundary: &Boundary) -> FeatureCollection {
+    let mut features: Vec<Feature> = fields
+        .iter()
+        .flat_map(|field| vec![polygon(field), center_line(field)])
+        .collect();
+    features.push(boundary_line(boundary));
+
+    FeatureCollection {
+        bbox: None,
+        features,
+        foreign_members: None,
+    }
+}
+
+fn properties(field: &Field) -> Map<String, serde_json::Value> {
+    let mut properties = Map::new();
+    properties.insert("team".to_string(), json!(field.team.name));
+    properties.insert("abbr".to_string(), json!(field.team.abbr));
+    properties.insert(
+        "color".to_string(),
+        json!(format!("#{}", hex::encode(field.team.color))),
+    );
+    properties
+}
+
+fn polygon(field: &Field) -> Feature {
+    let mut ring: Vec<Vec<f64>> = field
+        .field
+        .corners(field.field_bearing)
+        .iter()
+        .map(|corner| vec![corner.x, corner.y])
+        .collect();
+    ring.push(ring[0].clone());
+
+    Feature {
+        bbox: None,
+        geometry: Some(Geometry::new(Value::Polygon(vec![ring]))),
+        id: None,
+        properties: Some(properties(field)),
+        foreign_members: None,
+    }
+}
+
+fn center_line(field: &Field) -> Feature {
+    let line = field
+        .line
+        .iter()
+        .map(|coord| vec![coord.x, coord.y])
+        .collect();
+
+    Feature {
+        bbox: None,
+        geometry: Some(Geometry::new(Value::LineString(line))),
+        id: None,
+        properties: Some(properties(field)),
+        foreign_members: None,
+    }
+}
+
+fn boundary_line(boundary: &Boundary) -> Feature {
+    let line = boundary.coords().map(|coord| vec![coord.x, coord.y]).collect();
+
+    Feature {
+        bbox: None,
+        geometry: Some(Geometry::new(Value::LineString(line))),
+        id: None,
+        properties: None,
+        foreign_members: None,
+    }
+}
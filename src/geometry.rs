@@ -50,73 +50,119 @@ pub(crate) struct MercatorSegment {
     pub(crate) b: Mercator,
 }
 
+/// Arc-length step used by [`MercatorSegment::tessellate`], in Mercator units.
+const TESSELLATE_STEP: f64 = 0.0005;
+
 impl MercatorSegment {
     fn as_line(self) -> MercatorLine {
         MercatorLine::new(self.a, self.b)
     }
 
     pub(crate) fn intersection(self, line: MercatorLine) -> Option<Mercator> {
-        let intersection = self.as_line().intersection(line)?;
-        if self.a.x.min(self.b.x) <= intersection.x && intersection.x <= self.a.x.max(self.b.x) {
-            Some(intersection)
+        let this = self.as_line();
+        let intersection = this.intersection(line)?;
+        // A segment steeper than 45 degrees loses most of its precision in x (it can be
+        // near-vertical), so bound-check on whichever axis the segment actually spans.
+        let in_bounds = if this.dir.y.abs() > this.dir.x.abs() {
+            self.a.y.min(self.b.y) <= intersection.y && intersection.y <= self.a.y.max(self.b.y)
         } else {
-            None
-        }
+            self.a.x.min(self.b.x) <= intersection.x && intersection.x <= self.a.x.max(self.b.x)
+        };
+        in_bounds.then_some(intersection)
     }
 
     pub(crate) fn tessellate(self) -> Vec<Cartographic> {
         let line = self.as_line();
+        let length = self.a.distance(self.b);
         let mut l = Vec::new();
-        let d_x = 0.0005 / (line.slope.powi(2) + 1.0).sqrt();
-        let mut x = self.a.x.min(self.b.x);
-        let end = self.a.x.max(self.b.x);
-        while x < end {
-            l.push(
-                Mercator {
-                    x,
-                    y: line.slope * x + line.y_intercept,
-                }
-                .into(),
-            );
-            x += d_x;
+        let mut t = 0.0;
+        while t < length {
+            l.push(line.point_at(t).into());
+            t += TESSELLATE_STEP;
         }
-        l.push(
-            Mercator {
-                x: end,
-                y: line.slope * end + line.y_intercept,
-            }
-            .into(),
-        );
+        l.push(self.b.into());
         l
     }
 }
 
+/// A line through `origin` along `dir`, a unit vector, so stepping or intersecting along it
+/// works the same whether the line is vertical, horizontal, or anything in between.
 #[derive(Debug, Clone, Copy)]
 pub(crate) struct MercatorLine {
-    slope: f64,
-    y_intercept: f64,
+    origin: Mercator,
+    dir: Mercator,
 }
 
 impl MercatorLine {
     pub(crate) fn new(start: Mercator, end: Mercator) -> MercatorLine {
-        MercatorLine::from_slope(start.slope(end), start)
+        MercatorLine::from_direction(end - start, start)
     }
 
     pub(crate) fn from_slope(slope: f64, point: Mercator) -> MercatorLine {
+        MercatorLine::from_direction(Mercator { x: 1.0, y: slope }, point)
+    }
+
+    fn from_direction(dir: Mercator, origin: Mercator) -> MercatorLine {
+        let length = Mercator { x: 0.0, y: 0.0 }.distance(dir);
         MercatorLine {
-            slope,
-            y_intercept: point.y - slope * point.x,
+            origin,
+            dir: Mercator {
+                x: dir.x / length,
+                y: dir.y / length,
+            },
+        }
+    }
+
+    fn point_at(self, t: f64) -> Mercator {
+        Mercator {
+            x: self.origin.x + t * self.dir.x,
+            y: self.origin.y + t * self.dir.y,
         }
     }
 
     pub(crate) fn intersection(self, other: MercatorLine) -> Option<Mercator> {
-        if (self.slope - other.slope).abs() < (f64::EPSILON * self.slope.max(other.slope)) {
+        let denom = self.dir.x * other.dir.y - self.dir.y * other.dir.x;
+        if denom.abs() < f64::EPSILON {
             return None;
         }
-        let x = (other.y_intercept - self.y_intercept) / (self.slope - other.slope);
-        Some(Mercator {
-            x,
-            y: self.slope * x + self.y_intercept,
-        })
+        let t = ((other.origin.x - self.origin.x) * other.dir.y
+            - (other.origin.y - self.origin.y) * other.dir.x)
+            / denom;
+        Some(self.point_at(t))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn intersection_handles_vertical_segment() {
+        let vertical = MercatorSegment {
+            a: Mercator { x: 1.0, y: -1.0 },
+            b: Mercator { x: 1.0, y: 1.0 },
+        };
+        let horizontal = MercatorLine::from_slope(0.0, Mercator { x: 0.0, y: 0.0 });
+
+        let intersection = vertical.intersection(horizontal).expect("lines should cross");
+        assert!((intersection.x - 1.0).abs() < 1e-9);
+        assert!((intersection.y - 0.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn tessellate_handles_vertical_segment() {
+        let vertical = MercatorSegment {
+            a: Mercator { x: 2.0, y: -1.0 },
+            b: Mercator { x: 2.0, y: 1.0 },
+        };
+
+        let points = vertical.tessellate();
+        assert!(points.len() > 1);
+
+        // The last point is the segment's actual endpoint rather than an overshoot from the
+        // fixed step size.
+        let last: Mercator = (*points.last().unwrap()).into();
+        assert!((last.x - 2.0).abs() < 1e-9);
+        assert!((last.y - 1.0).abs() < 1e-9);
     }
 }
\ No newline at end of file
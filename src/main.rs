@@ -2,12 +2,17 @@
 #![allow(clippy::map_entry)] // https://github.com/rust-lang/rust-clippy/issues/1450
 
 mod geo;
+mod geojson;
 mod image;
+mod manifest;
 mod ord;
+mod query;
+mod route;
 mod survey;
 mod template;
 
 use crate::geo::*;
+use crate::manifest::Manifest;
 use crate::ord::OrdF64;
 use crate::survey::Survey;
 use crate::template::*;
@@ -19,7 +24,7 @@ use lazy_static::lazy_static;
 use std::borrow::Cow;
 use std::collections::HashMap;
 use std::fs::{self, File};
-use std::io::{prelude::*, BufReader, ErrorKind};
+use std::io::{prelude::*, BufReader, Cursor, ErrorKind};
 use std::path::Path;
 use std::process::Command;
 use uom::si::f64::Length;
@@ -27,11 +32,29 @@ use uom::si::length::{foot, meter};
 use zip::write::{FileOptions, ZipWriter};
 use zip::CompressionMethod;
 
+/// Handles the `nearest <lat,lng>` subcommand if that's what was invoked, so both `main`
+/// variants below dispatch to it the same way. Returns `None` when a different (or no)
+/// subcommand was given, leaving the caller to fall through to its own behavior.
+fn try_nearest() -> Option<Result<()>> {
+    let mut args = std::env::args().skip(1);
+    if args.next().as_deref() != Some("nearest") {
+        return None;
+    }
+    Some((|| {
+        let point = args.next().ok_or_else(|| anyhow::anyhow!("usage: nearest <lat,lng>"))?;
+        query::nearest(&point)
+    })())
+}
+
 #[cfg(feature = "hotwatch")]
 fn main() -> Result<()> {
     use hotwatch::blocking::{Flow, Hotwatch};
     use hotwatch::Event;
 
+    if let Some(result) = try_nearest() {
+        return result;
+    }
+
     if std::env::args().any(|arg| arg == "watch") {
         fn handler(event: Event) -> Flow {
             eprint!("{:?} ... ", event);
@@ -61,25 +84,40 @@ fn main() -> Result<()> {
 
 #[cfg(not(feature = "hotwatch"))]
 fn main() -> Result<()> {
+    if let Some(result) = try_nearest() {
+        return result;
+    }
+
     run()
 }
 
-fn run() -> Result<()> {
-    let revision = match option_env!("COMMIT_REF") {
-        Some(rev) => Cow::from(rev),
-        None => String::from_utf8(
-            Command::new("git")
-                .args(&["rev-parse", "HEAD"])
-                .output()?
-                .stdout,
-        )?
-        .into(),
+/// Loads `teams.csv` and the per-team survey KML, producing the rendered `Field`s, the
+/// label/field PNGs keyed by filename, and the parsed `Boundary`. Shared by [`run`] and the
+/// `nearest` subcommand so both see the same set of fields.
+///
+/// `persist` controls whether this touches disk beyond reading inputs: `run` passes `true` so
+/// unchanged images are read from (and changed ones written to) the `site/` cache and manifest;
+/// `nearest` passes `false` so a read-only query never mutates `site/manifest.txt` or
+/// `site/files/`, regenerating every image in memory instead.
+///
+/// Only image rendering is gated on the manifest; every `Field` is still parsed and
+/// reconstructed on every call regardless of `persist`. That's a deliberate scope cut, not an
+/// oversight: rasterizing the label/field PNGs is the actual expensive step, while parsing a
+/// survey KML and running `Boundary::limit` over it is cheap in comparison, and caching `Field`
+/// itself across runs would need a way to serialize it into the manifest, which nothing in this
+/// crate currently provides.
+pub(crate) fn load_fields(persist: bool) -> Result<(Vec<Field>, HashMap<String, Vec<u8>>, Boundary)> {
+    let site_dir = root().join("site");
+    let files_dir = site_dir.join("files");
+    let mut manifest = if persist {
+        fs::create_dir_all(&files_dir)?;
+        Some(Manifest::load(&site_dir.join("manifest.txt")))
+    } else {
+        None
     };
-    let revision = revision.trim();
 
-    let boundary = Boundary::load(BufReader::new(File::open(
-        root().join("data").join("boundary.kml"),
-    )?));
+    let boundary_kml = fs::read_to_string(root().join("data").join("boundary.kml"))?;
+    let boundary = Boundary::load(BufReader::new(boundary_kml.as_bytes()));
 
     let mut fields = Vec::new();
     let mut images = HashMap::new();
@@ -88,7 +126,8 @@ fn run() -> Result<()> {
         .lines()
         .skip(1)
     {
-        let team = Team::from_str(&line?);
+        let line = line?;
+        let team = Team::from_str(&line);
         let kml = match fs::read_to_string(
             root().join("survey").join(&team.name).with_extension("kml"),
         ) {
@@ -101,10 +140,32 @@ fn run() -> Result<()> {
         let line = boundary.limit(&survey).unwrap();
         let center = (line.start + line.end) / 2.0;
 
-        images.insert(format!("{}.png", team.name), image::label(&team)?);
+        let label_filename = format!("{}.png", team.name);
+        images.insert(
+            label_filename.clone(),
+            match &mut manifest {
+                Some(manifest) => {
+                    let changed = manifest.changed(
+                        &format!("image:{}", label_filename),
+                        &[team.name.as_bytes(), team.abbr.as_bytes(), &team.color],
+                    );
+                    cached_image(&files_dir, &label_filename, changed, || image::label(&team))?
+                }
+                None => image::label(&team)?,
+            },
+        );
+
         let field_filename = format!("{}.png", hex::encode(team.color));
         if !images.contains_key(&field_filename) {
-            images.insert(field_filename, image::field(&team)?);
+            let image = match &mut manifest {
+                Some(manifest) => {
+                    let changed =
+                        manifest.changed(&format!("image:{}", field_filename), &[&team.color]);
+                    cached_image(&files_dir, &field_filename, changed, || image::field(&team))?
+                }
+                None => image::field(&team)?,
+            };
+            images.insert(field_filename, image);
         }
 
         let field_length = line
@@ -134,27 +195,69 @@ fn run() -> Result<()> {
         });
     }
 
-    let site_dir = root().join("site");
-    let files_dir = site_dir.join("files");
-    fs::create_dir_all(&files_dir)?;
+    if let Some(manifest) = &manifest {
+        manifest.save(&site_dir.join("manifest.txt"))?;
+    }
+    Ok((fields, images, boundary))
+}
 
-    let mut zip = ZipWriter::new(File::create(site_dir.join("20020.kmz"))?);
-    fs::write(
-        site_dir.join("20020.kml"),
-        Output {
-            kmz: false,
-            revision: &revision,
-            fields: &fields,
+/// Returns `image`'s bytes, reusing the cached PNG at `files_dir/filename` when `changed` is
+/// `false` and that cache entry actually exists, regenerating (and re-caching) otherwise.
+fn cached_image(
+    files_dir: &Path,
+    filename: &str,
+    changed: bool,
+    image: impl FnOnce() -> Result<Vec<u8>>,
+) -> Result<Vec<u8>> {
+    let path = files_dir.join(filename);
+    if !changed {
+        if let Ok(cached) = fs::read(&path) {
+            return Ok(cached);
         }
-        .render()?
-        .as_bytes(),
-    )?;
+    }
+    let bytes = image()?;
+    fs::write(&path, &bytes)?;
+    Ok(bytes)
+}
+
+fn run() -> Result<()> {
+    let revision = match option_env!("COMMIT_REF") {
+        Some(rev) => Cow::from(rev),
+        None => String::from_utf8(
+            Command::new("git")
+                .args(&["rev-parse", "HEAD"])
+                .output()?
+                .stdout,
+        )?
+        .into(),
+    };
+    let revision = revision.trim();
+
+    let (fields, images, boundary) = load_fields(true)?;
+    let route = route::plan(&fields);
+
+    let site_dir = root().join("site");
+
+    let geojson = geojson::build(&fields, &boundary).to_string();
+    write_if_changed(&site_dir.join("20020.geojson"), geojson.as_bytes())?;
+
+    let kml = Output {
+        kmz: false,
+        revision: &revision,
+        fields: &fields,
+        route: route.as_ref(),
+    }
+    .render()?;
+    write_if_changed(&site_dir.join("20020.kml"), kml.as_bytes())?;
+
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
     zip.start_file("doc.kml", FileOptions::default())?;
     zip.write_all(
         Output {
             kmz: true,
             revision: &revision,
             fields: &fields,
+            route: route.as_ref(),
         }
         .render()?
         .as_bytes(),
@@ -168,7 +271,18 @@ fn run() -> Result<()> {
         zip.write_all(&image)?;
     }
 
-    zip.finish()?;
+    let kmz = zip.finish()?.into_inner();
+    write_if_changed(&site_dir.join("20020.kmz"), &kmz)?;
+
+    Ok(())
+}
+
+/// Writes `contents` to `path` only if it differs from what's already there, so timestamps and
+/// downstream syncs aren't churned when a run produces byte-identical output.
+fn write_if_changed(path: &Path, contents: &[u8]) -> Result<()> {
+    if fs::read(path).ok().as_deref() != Some(contents) {
+        fs::write(path, contents)?;
+    }
     Ok(())
 }
 
@@ -186,6 +300,10 @@ struct Team {
 }
 
 impl Team {
+    pub(crate) fn hex(&self) -> String {
+        hex::encode(self.color)
+    }
+
     fn from_str(s: &str) -> Team {
         let mut iter = s.split(',');
         Team {
@@ -223,6 +341,35 @@ impl LatLonBox {
         }
     }
 
+    pub(crate) fn center(self) -> Coordinate {
+        Coordinate {
+            x: (self.east + self.west) / 2.0,
+            y: (self.north + self.south) / 2.0,
+        }
+    }
+
+    /// The box's four corners (north-east, north-west, south-west, south-east), rotated about
+    /// its center by `bearing` degrees clockwise from north to match the KML `<rotation>` this
+    /// box is rendered with.
+    pub(crate) fn corners(self, bearing: f64) -> [Coordinate; 4] {
+        let center = self.center();
+        let theta = -bearing.to_radians();
+        let (sin, cos) = theta.sin_cos();
+        [
+            (self.north, self.east),
+            (self.north, self.west),
+            (self.south, self.west),
+            (self.south, self.east),
+        ]
+        .map(|(y, x)| {
+            let (dx, dy) = (x - center.x, y - center.y);
+            Coordinate {
+                x: center.x + dx * cos - dy * sin,
+                y: center.y + dx * sin + dy * cos,
+            }
+        })
+    }
+
     fn adjust_width(self, at: Coordinate, width: Length) -> LatLonBox {
         let lon = (self.east + self.west) / 2.0;
         let angle = Point::from(at)
@@ -238,9 +385,13 @@ impl LatLonBox {
 }
 
 #[derive(Debug)]
-struct Boundary(LineString);
+pub(crate) struct Boundary(LineString);
 
 impl Boundary {
+    pub(crate) fn coords(&self) -> impl Iterator<Item = Coordinate> + '_ {
+        self.0.coords().copied()
+    }
+
     fn load(input: impl BufRead) -> Boundary {
         Boundary(
             input
@@ -282,3 +433,55 @@ impl Boundary {
         Some(Line { start, end })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square() -> LatLonBox {
+        LatLonBox {
+            north: 1.0,
+            south: -1.0,
+            east: 1.0,
+            west: -1.0,
+        }
+    }
+
+    #[test]
+    fn corners_with_no_bearing_are_the_box_corners_wound_ccw() {
+        let corners = square().corners(0.0);
+        assert_eq!(
+            corners,
+            [
+                Coordinate { x: 1.0, y: 1.0 },
+                Coordinate { x: -1.0, y: 1.0 },
+                Coordinate { x: -1.0, y: -1.0 },
+                Coordinate { x: 1.0, y: -1.0 },
+            ]
+        );
+
+        // Shoelace formula: a positive signed area means the ring is wound
+        // counter-clockwise, matching the winding GeoJSON expects for an exterior ring.
+        let signed_area: f64 = corners
+            .iter()
+            .zip(corners.iter().cycle().skip(1))
+            .map(|(a, b)| a.x * b.y - b.x * a.y)
+            .sum();
+        assert!(signed_area > 0.0);
+    }
+
+    #[test]
+    fn corners_rotate_clockwise_with_bearing() {
+        let corners = square().corners(90.0);
+        let expected = [
+            Coordinate { x: 1.0, y: -1.0 },
+            Coordinate { x: 1.0, y: 1.0 },
+            Coordinate { x: -1.0, y: 1.0 },
+            Coordinate { x: -1.0, y: -1.0 },
+        ];
+        for (corner, expected) in corners.iter().zip(expected.iter()) {
+            assert!((corner.x - expected.x).abs() < 1e-9);
+            assert!((corner.y - expected.y).abs() < 1e-9);
+        }
+    }
+}
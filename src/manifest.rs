@@ -0,0 +1,50 @@
+use anyhow::Result;
+use itertools::Itertools;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::Path;
+
+/// Tracks a content hash per artifact across runs, so `run` can skip re-rendering any label or
+/// field image whose inputs are unchanged since the last invocation (important because
+/// `hotwatch` triggers a full rebuild on every file event). `Field`s are not tracked here and
+/// are rebuilt on every run.
+#[derive(Debug, Default)]
+pub(crate) struct Manifest(HashMap<String, u64>);
+
+impl Manifest {
+    pub(crate) fn load(path: &Path) -> Manifest {
+        let contents = fs::read_to_string(path).unwrap_or_default();
+        Manifest(
+            contents
+                .lines()
+                .filter_map(|line| line.rsplit_once(' '))
+                .filter_map(|(key, hash)| Some((key.to_string(), hash.parse().ok()?)))
+                .collect(),
+        )
+    }
+
+    pub(crate) fn save(&self, path: &Path) -> Result<()> {
+        fs::write(
+            path,
+            self.0
+                .iter()
+                .sorted_by_key(|(key, _)| key.to_owned())
+                .map(|(key, hash)| format!("{} {}", key, hash))
+                .join("\n"),
+        )?;
+        Ok(())
+    }
+
+    /// Hashes `inputs` under `key` and reports whether that differs from the hash on record,
+    /// updating the record either way.
+    pub(crate) fn changed(&mut self, key: &str, inputs: &[&[u8]]) -> bool {
+        let mut hasher = DefaultHasher::new();
+        for input in inputs {
+            input.hash(&mut hasher);
+        }
+        let hash = hasher.finish();
+        self.0.insert(key.to_string(), hash) != Some(hash)
+    }
+}
@@ -0,0 +1,39 @@
+use crate::geo::*;
+use crate::ord::OrdF64;
+use crate::{load_fields, Field};
+use anyhow::{anyhow, Context, Result};
+
+/// Parses a `lat,lng` pair such as `42.36,-71.06`, erroring cleanly on malformed input.
+fn parse_point(s: &str) -> Result<Point> {
+    let (lat, lng) = s
+        .split_once(',')
+        .ok_or_else(|| anyhow!("expected `lat,lng`, got `{}`", s))?;
+    let lat: f64 = lat.trim().parse().context("invalid latitude")?;
+    let lng: f64 = lng.trim().parse().context("invalid longitude")?;
+    Ok(Point::new(lng, lat))
+}
+
+/// Runs the `nearest <lat,lng>` subcommand: prints every `Field` ordered by great-circle
+/// distance from the given point, nearest first.
+pub(crate) fn nearest(arg: &str) -> Result<()> {
+    let point = parse_point(arg)?;
+    let (fields, _, _) = load_fields(false)?;
+
+    let mut by_distance: Vec<(Field, f64)> = fields
+        .into_iter()
+        .map(|field| {
+            let distance = point.haversine_distance(&Point::from(field.field.center()));
+            (field, distance)
+        })
+        .collect();
+    by_distance.sort_by_key(|(_, distance)| OrdF64(*distance));
+
+    for (field, distance) in by_distance {
+        println!(
+            "{:>10.1}m  {} ({})",
+            distance, field.team.name, field.team.abbr
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,139 @@
+use crate::geo::*;
+use crate::ord::OrdF64;
+use crate::Field;
+use itertools::Itertools;
+
+/// An ordered tour connecting every field center, plus its total great-circle length.
+#[derive(Debug)]
+pub(crate) struct Route {
+    pub(crate) points: Vec<Coordinate>,
+    pub(crate) length: f64,
+}
+
+/// Cap on 2-opt passes so a pathological input can't spin forever; a full pass with no
+/// improving swap stops the search long before this is reached in practice.
+const MAX_PASSES: usize = 50;
+
+/// Orders every field's center into a single tour: a nearest-neighbor construction, improved by
+/// bounded 2-opt passes that reverse a segment whenever doing so shortens the tour.
+pub(crate) fn plan(fields: &[Field]) -> Option<Route> {
+    let centers: Vec<Coordinate> = fields.iter().map(|field| field.field.center()).collect();
+    order(centers)
+}
+
+fn order(centers: Vec<Coordinate>) -> Option<Route> {
+    if centers.len() < 2 {
+        return None;
+    }
+
+    let mut order = nearest_neighbor(&centers);
+    two_opt(&centers, &mut order);
+
+    Some(Route {
+        length: tour_length(&centers, &order),
+        points: order.into_iter().map(|i| centers[i]).collect(),
+    })
+}
+
+fn distance(centers: &[Coordinate], a: usize, b: usize) -> f64 {
+    Point::from(centers[a]).haversine_distance(&Point::from(centers[b]))
+}
+
+fn tour_length(centers: &[Coordinate], order: &[usize]) -> f64 {
+    order
+        .iter()
+        .tuple_windows()
+        .map(|(&a, &b)| distance(centers, a, b))
+        .sum()
+}
+
+fn nearest_neighbor(centers: &[Coordinate]) -> Vec<usize> {
+    let mut visited = vec![false; centers.len()];
+    let mut order = Vec::with_capacity(centers.len());
+
+    let mut current = 0;
+    visited[current] = true;
+    order.push(current);
+
+    while order.len() < centers.len() {
+        current = (0..centers.len())
+            .filter(|&i| !visited[i])
+            .min_by_key(|&i| OrdF64(distance(centers, current, i)))
+            .unwrap();
+        visited[current] = true;
+        order.push(current);
+    }
+
+    order
+}
+
+fn two_opt(centers: &[Coordinate], order: &mut [usize]) {
+    for _ in 0..MAX_PASSES {
+        let mut improved = false;
+        for i in 0..order.len() - 1 {
+            for j in i + 1..order.len() - 1 {
+                let (a, b) = (order[i], order[i + 1]);
+                let (c, d) = (order[j], order[j + 1]);
+                let delta = (distance(centers, a, c) + distance(centers, b, d))
+                    - (distance(centers, a, b) + distance(centers, c, d));
+                if delta < 0.0 {
+                    order[i + 1..=j].reverse();
+                    improved = true;
+                }
+            }
+        }
+        if !improved {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn coord(x: f64, y: f64) -> Coordinate {
+        Coordinate { x, y }
+    }
+
+    #[test]
+    fn tour_visits_every_center_exactly_once() {
+        let centers = vec![
+            coord(0.0, 0.0),
+            coord(1.0, 0.0),
+            coord(1.0, 1.0),
+            coord(0.0, 1.0),
+            coord(0.5, 2.0),
+        ];
+
+        let route = order(centers.clone()).expect("enough centers for a tour");
+        assert_eq!(route.points.len(), centers.len());
+        for center in &centers {
+            let visits = route
+                .points
+                .iter()
+                .filter(|point| (point.x - center.x).abs() < 1e-9 && (point.y - center.y).abs() < 1e-9)
+                .count();
+            assert_eq!(visits, 1, "{:?} should be visited exactly once", center);
+        }
+    }
+
+    #[test]
+    fn two_opt_never_increases_tour_length() {
+        let centers = vec![
+            coord(0.0, 0.0),
+            coord(2.0, 0.0),
+            coord(2.0, 2.0),
+            coord(0.0, 2.0),
+            coord(1.0, 0.1),
+            coord(1.9, 1.9),
+        ];
+        let mut order: Vec<usize> = (0..centers.len()).collect();
+        let before = tour_length(&centers, &order);
+
+        two_opt(&centers, &mut order);
+        let after = tour_length(&centers, &order);
+
+        assert!(after <= before + 1e-9, "2-opt made the tour longer: {} > {}", after, before);
+    }
+}
@@ -0,0 +1,25 @@
+use crate::geo::*;
+use crate::route::Route;
+use crate::Team;
+use askama::Template;
+
+/// A single surveyed team's field, ready to render into KML: its ground overlay, its label
+/// overlay, and its interpolated center line.
+pub(crate) struct Field {
+    pub(crate) team: Team,
+    pub(crate) field: crate::LatLonBox,
+    pub(crate) field_bearing: f64,
+    pub(crate) line: Vec<Coordinate>,
+    pub(crate) label: crate::LatLonBox,
+    pub(crate) label_bearing: f64,
+    pub(crate) label_region: crate::LatLonBox,
+}
+
+#[derive(Template)]
+#[template(path = "kml.xml", escape = "xml")]
+pub(crate) struct Output<'a> {
+    pub(crate) kmz: bool,
+    pub(crate) revision: &'a str,
+    pub(crate) fields: &'a [Field],
+    pub(crate) route: Option<&'a Route>,
+}